@@ -1,15 +1,18 @@
 use std::io;
 
-use chrono::Duration;
+use chrono::{DateTime, Duration, Utc};
 use log::warn;
 use smallvec::SmallVec;
 
 use crate::composed::key::KeyDetails;
 use crate::composed::signed_key::{SignedPublicKey, SignedSecretKey};
-use crate::errors::Result;
-use crate::packet::KeyFlags;
+use crate::errors::{Error, Result};
+use crate::packet::{KeyFlags, SignatureType};
 use crate::ser::Serialize;
-use crate::types::{PublicKeyTrait, SignedUser, SignedUserAttribute};
+use crate::types::{
+    Fingerprint, HashAlgorithm, PublicKeyAlgorithm, PublicKeyTrait, PublicParams, RevocationCode,
+    SignedUser, SignedUserAttribute, UserId,
+};
 use crate::{packet, ArmorOptions};
 
 /// Shared details between secret and public keys.
@@ -21,6 +24,174 @@ pub struct SignedKeyDetails {
     pub user_attributes: Vec<SignedUserAttribute>,
 }
 
+/// The revocation status of a key, as evaluated against a reference point in time.
+///
+/// See [`SignedKeyDetails::revocation_status`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum RevocationStatus<'a> {
+    /// The key is revoked, effective as of the reference time.
+    Revoked(Vec<&'a packet::Signature>),
+    /// The key carries a hard revocation signature, but its creation time is after the
+    /// reference time, so it has not taken effect yet. Treat with suspicion.
+    CouldBe(Vec<&'a packet::Signature>),
+    /// No applicable revocation signature was found.
+    NotRevoked,
+}
+
+/// A policy for deciding which cryptographic choices on a certificate are acceptable.
+///
+/// Implement this trait to customize [`SignedKeyDetails::verify_with_policy`] — for example, to
+/// refuse keys that only carry SHA-1 self-signatures, or to require a minimum RSA key size.
+pub trait Policy {
+    /// Returns `Ok(())` if `hash` is an acceptable hash algorithm for a binding signature.
+    fn accept_hash(&self, hash: HashAlgorithm) -> Result<()>;
+
+    /// Returns `Ok(())` if `alg` is an acceptable public key algorithm.
+    fn accept_pk(&self, alg: PublicKeyAlgorithm) -> Result<()>;
+
+    /// Returns `Ok(())` if `sig` as a whole is acceptable.
+    ///
+    /// The default implementation only checks `sig`'s hash algorithm via [`Self::accept_hash`].
+    fn accept_signature(&self, sig: &packet::Signature) -> Result<()> {
+        self.accept_hash(sig.hash_alg())
+    }
+
+    /// Returns `Ok(())` if `key`'s parameters (such as its size, for RSA) are acceptable.
+    ///
+    /// The default implementation only checks `key`'s algorithm via [`Self::accept_pk`];
+    /// override to reject e.g. short RSA moduli.
+    fn accept_key(&self, key: &impl PublicKeyTrait) -> Result<()> {
+        self.accept_pk(key.algorithm())
+    }
+}
+
+/// The default [`Policy`]: rejects MD5 and SHA-1 binding signatures, and RSA keys smaller than
+/// [`StandardPolicy::MIN_RSA_BITS`].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct StandardPolicy;
+
+impl StandardPolicy {
+    /// The minimum accepted RSA modulus size, in bits.
+    pub const MIN_RSA_BITS: usize = 2048;
+}
+
+impl Policy for StandardPolicy {
+    fn accept_hash(&self, hash: HashAlgorithm) -> Result<()> {
+        match hash {
+            HashAlgorithm::MD5 | HashAlgorithm::SHA1 => Err(Error::Message(format!(
+                "hash algorithm {:?} is not accepted by policy",
+                hash
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    fn accept_pk(&self, _alg: PublicKeyAlgorithm) -> Result<()> {
+        Ok(())
+    }
+
+    fn accept_key(&self, key: &impl PublicKeyTrait) -> Result<()> {
+        if let PublicParams::RSA { n, .. } = key.public_params() {
+            if n.len() * 8 < Self::MIN_RSA_BITS {
+                return Err(Error::Message(format!(
+                    "RSA key is smaller than the minimum accepted size of {} bits",
+                    Self::MIN_RSA_BITS
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The outcome of verifying a single third-party certification signature on a user ID.
+///
+/// See [`SignedKeyDetails::verify_certifications`].
+#[derive(Debug, Clone)]
+pub struct CertificationResult {
+    /// The user ID this certification was made over.
+    pub user_id: UserId,
+    /// The fingerprint of the certifier whose key was matched to the certification's issuer.
+    pub certifier: Fingerprint,
+    /// The certification's trust level and amount, if it carries a `TrustSignature` subpacket.
+    pub trust: Option<(u8, u8)>,
+    /// Whether the certification verified successfully against the certifier's key.
+    pub verified: bool,
+}
+
+/// A [`SignedUser`] together with the particular self-signature chosen to represent its
+/// current binding, as of the reference time passed to [`SignedKeyDetails::users_at`].
+#[derive(Debug, Clone, Copy)]
+pub struct UserBinding<'a> {
+    user: &'a SignedUser,
+    signature: &'a packet::Signature,
+}
+
+impl<'a> UserBinding<'a> {
+    /// The user ID this binding applies to.
+    pub fn id(&self) -> &'a UserId {
+        &self.user.id
+    }
+
+    /// The self-signature chosen as this binding's newest valid one.
+    pub fn signature(&self) -> &'a packet::Signature {
+        self.signature
+    }
+
+    /// Whether this binding's signature marks its user ID as the primary one.
+    pub fn is_primary(&self) -> bool {
+        self.signature.primary_user_id().unwrap_or(false)
+    }
+
+    /// The key flags carried by this binding's signature.
+    pub fn key_flags(&self) -> KeyFlags {
+        self.signature.key_flags()
+    }
+}
+
+/// An iterator over a key's [`UserBinding`]s at a point in time, with chainable filters.
+///
+/// Obtained from [`SignedKeyDetails::users_at`].
+pub struct UserIter<'a> {
+    bindings: std::vec::IntoIter<UserBinding<'a>>,
+}
+
+impl<'a> Iterator for UserIter<'a> {
+    type Item = UserBinding<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.bindings.next()
+    }
+}
+
+impl<'a> UserIter<'a> {
+    fn new(bindings: Vec<UserBinding<'a>>) -> Self {
+        UserIter {
+            bindings: bindings.into_iter(),
+        }
+    }
+
+    /// Keeps only bindings whose signature carries all of `flags`.
+    pub fn with_key_flag(self, flags: KeyFlags) -> UserIter<'a> {
+        UserIter::new(
+            self.filter(|binding| binding.key_flags().contains(flags))
+                .collect(),
+        )
+    }
+
+    /// Picks the primary user binding: the one whose signature is marked primary, or, if none
+    /// is, the first remaining binding.
+    pub fn primary(self) -> Option<UserBinding<'a>> {
+        let mut bindings: Vec<_> = self.collect();
+        let primary_pos = bindings.iter().position(UserBinding::is_primary);
+
+        match primary_pos {
+            Some(pos) => Some(bindings.remove(pos)),
+            None => bindings.into_iter().next(),
+        }
+    }
+}
+
 impl SignedKeyDetails {
     pub fn new(
         revocation_signatures: Vec<packet::Signature>,
@@ -71,58 +242,367 @@ impl SignedKeyDetails {
             .cloned()
     }
 
-    fn verify_users(&self, key: &impl PublicKeyTrait) -> Result<()> {
+    /// Returns whether `sig` is exportable: it does not carry an `Exportable Certification`
+    /// subpacket set to `false`. A signature with no such subpacket is exportable by default;
+    /// local-only certifications (e.g. produced by `gpg --lsign`) set it to `false` to keep the
+    /// certification off of keyservers.
+    fn exportable(sig: &packet::Signature) -> bool {
+        sig.exportable_certification().unwrap_or(true)
+    }
+
+    /// Returns whether `sig` is a "hard" revocation, i.e. one that applies regardless of when it
+    /// was created: either no reason was given (no subpacket at all, or an explicit
+    /// `RevocationCode::NoReason`), or the stated reason is that the key material itself was
+    /// compromised.
+    fn is_hard_revocation(sig: &packet::Signature) -> bool {
+        match sig.revocation_reason_code() {
+            None => true,
+            Some(code) => {
+                *code == RevocationCode::NoReason || *code == RevocationCode::KeyCompromised
+            }
+        }
+    }
+
+    /// Returns whether `sig`'s Issuer/IssuerFingerprint subpacket matches `key`, i.e. whether
+    /// `key` is (claimed to be) the one that produced `sig`. This is a claim only — the caller
+    /// still needs to cryptographically verify `sig` to confirm it.
+    fn issued_by(sig: &packet::Signature, key: &impl PublicKeyTrait) -> bool {
+        sig.issuer_fingerprint()
+            .map(|fp| *fp == key.fingerprint())
+            .or_else(|| sig.issuer().map(|id| *id == key.key_id()))
+            .unwrap_or(false)
+    }
+
+    /// Sorts an already-verified revocation signature into `revoked` or `could_be`, per the rules
+    /// documented on [`Self::revocation_status`].
+    fn bucket_revocation<'s>(
+        sig: &'s packet::Signature,
+        at: DateTime<Utc>,
+        revoked: &mut Vec<&'s packet::Signature>,
+        could_be: &mut Vec<&'s packet::Signature>,
+    ) {
+        let created = sig.created();
+
+        if Self::is_hard_revocation(sig) {
+            match created {
+                Some(created) if *created > at => could_be.push(sig),
+                _ => revoked.push(sig),
+            }
+        } else if matches!(created, Some(created) if *created <= at) {
+            revoked.push(sig);
+        }
+    }
+
+    /// Evaluates the revocation status of this key, at the reference time `at`.
+    ///
+    /// This inspects `revocation_signatures`, as well as any certification-revocation
+    /// signatures found among `users`. Each candidate signature is verified against `key`; only
+    /// signatures that verify are considered.
+    ///
+    /// A "hard" revocation (no reason given, or `KeyCompromised`) applies regardless of `at`: if
+    /// its creation time is at or before `at` the key is [`RevocationStatus::Revoked`], otherwise
+    /// it is only [`RevocationStatus::CouldBe`] revoked (the revocation exists, but has not taken
+    /// effect yet at `at`). A "soft" revocation (`KeySuperseded`, `KeyRetired`, or
+    /// `UserIdNoLongerValid`) only counts towards [`RevocationStatus::Revoked`] if its creation
+    /// time is at or before `at`; otherwise it is ignored entirely.
+    pub fn revocation_status(&self, key: &impl PublicKeyTrait, at: DateTime<Utc>) -> RevocationStatus<'_> {
+        let mut revoked = Vec::new();
+        let mut could_be = Vec::new();
+
+        // Key revocations hash the primary key packet alone, so they verify directly against
+        // `key`.
+        for sig in &self.revocation_signatures {
+            if sig.verify_key(key).is_err() {
+                continue;
+            }
+
+            Self::bucket_revocation(sig, at, &mut revoked, &mut could_be);
+        }
+
+        // Certification revocations hash the primary key packet *and* the user ID they revoke,
+        // so they must be verified against `key` together with the user they were found on —
+        // verifying against `key` alone would accept a revocation lifted from a different user ID
+        // on the same key.
+        for user in &self.users {
+            for sig in user
+                .signatures
+                .iter()
+                .filter(|sig| sig.typ() == SignatureType::CertRevocation)
+            {
+                if sig.verify_certification(key, key, &user.id).is_err() {
+                    continue;
+                }
+
+                Self::bucket_revocation(sig, at, &mut revoked, &mut could_be);
+            }
+        }
+
+        if !revoked.is_empty() {
+            RevocationStatus::Revoked(revoked)
+        } else if !could_be.is_empty() {
+            RevocationStatus::CouldBe(could_be)
+        } else {
+            RevocationStatus::NotRevoked
+        }
+    }
+
+    pub fn verify(&self, key: &impl PublicKeyTrait) -> Result<()> {
+        self.verify_with_policy(key, &StandardPolicy, Utc::now())
+    }
+
+    /// Verifies `sigs` as self-signatures binding `id` to `key`, skipping any signature rejected
+    /// by `policy`. Returns an error if any policy-conforming signature fails to verify, or if
+    /// no signature conforms to `policy` at all.
+    ///
+    /// A binding signature hashes the primary key packet *and* the user ID/attribute packet it
+    /// binds, so `id` is passed through to [`packet::Signature::verify_certification`] alongside
+    /// `key` — verifying against `key` alone would also accept a binding signature lifted from a
+    /// different user ID on the same key.
+    fn verify_policy_conforming_binding<'s, D>(
+        sigs: impl Iterator<Item = &'s packet::Signature>,
+        key: &impl PublicKeyTrait,
+        policy: &impl Policy,
+        id: &D,
+    ) -> Result<()>
+    where
+        D: Serialize + std::fmt::Display,
+    {
+        let mut accepted = false;
+
+        for sig in sigs {
+            if policy.accept_signature(sig).is_err() {
+                continue;
+            }
+
+            sig.verify_certification(key, key, id)?;
+            accepted = true;
+        }
+
+        if accepted {
+            Ok(())
+        } else {
+            Err(Error::Message(format!(
+                "no policy-conforming signature found for {id}"
+            )))
+        }
+    }
+
+    /// Returns whether `sig` had already been created at the reference time `at` — signatures
+    /// with no creation time subpacket are treated as always eligible.
+    fn created_at_or_before(sig: &packet::Signature, at: DateTime<Utc>) -> bool {
+        match sig.created() {
+            Some(created) => *created <= at,
+            None => true,
+        }
+    }
+
+    fn verify_users_with_policy(
+        &self,
+        key: &impl PublicKeyTrait,
+        policy: &impl Policy,
+        at: DateTime<Utc>,
+    ) -> Result<()> {
         for user in &self.users {
-            user.verify(key)?;
+            let sigs = user
+                .signatures
+                .iter()
+                .filter(|sig| Self::created_at_or_before(sig, at));
+            Self::verify_policy_conforming_binding(sigs, key, policy, &user.id)?;
         }
 
         Ok(())
     }
 
-    fn verify_attributes(&self, key: &impl PublicKeyTrait) -> Result<()> {
+    fn verify_attributes_with_policy(
+        &self,
+        key: &impl PublicKeyTrait,
+        policy: &impl Policy,
+        at: DateTime<Utc>,
+    ) -> Result<()> {
         for attr in &self.user_attributes {
-            attr.verify(key)?;
+            let sigs = attr
+                .signatures
+                .iter()
+                .filter(|sig| Self::created_at_or_before(sig, at));
+            Self::verify_policy_conforming_binding(sigs, key, policy, &attr.attr)?;
         }
 
         Ok(())
     }
 
-    fn verify_revocation_signatures(&self, key: &impl PublicKeyTrait) -> Result<()> {
+    fn verify_revocation_signatures_with_policy(
+        &self,
+        key: &impl PublicKeyTrait,
+        policy: &impl Policy,
+    ) -> Result<()> {
         for sig in &self.revocation_signatures {
+            if policy.accept_signature(sig).is_err() {
+                continue;
+            }
             sig.verify_key(key)?;
         }
 
         Ok(())
     }
 
-    fn verify_direct_signatures(&self, key: &impl PublicKeyTrait) -> Result<()> {
+    fn verify_direct_signatures_with_policy(
+        &self,
+        key: &impl PublicKeyTrait,
+        policy: &impl Policy,
+    ) -> Result<()> {
         for sig in &self.direct_signatures {
+            if policy.accept_signature(sig).is_err() {
+                continue;
+            }
             sig.verify_key(key)?;
         }
 
         Ok(())
     }
 
-    pub fn verify(&self, key: &impl PublicKeyTrait) -> Result<()> {
-        self.verify_users(key)?;
-        self.verify_attributes(key)?;
-        self.verify_revocation_signatures(key)?;
-        self.verify_direct_signatures(key)?;
+    /// Verifies third-party certifications on this key's users, against a set of candidate
+    /// certifier keys.
+    ///
+    /// Unlike [`Self::verify`], which only checks self-signatures, this looks at signatures on
+    /// each [`SignedUser`] whose issuer is *not* `target_key` itself, and tries to match them to
+    /// one of `certifiers` by the signature's Issuer/IssuerFingerprint subpacket. Each match is
+    /// cryptographically verified via `sig.verify_certification(certifier, target_key, &user.id)`
+    /// — `certifier` supplies the key whose signature is being checked, while `target_key` and
+    /// `user.id` supply the data that was actually hashed (the certification binds a user ID to
+    /// `target_key`, not to `certifier`; swapping the two would always fail to verify, since
+    /// `certifier` never signed anything naming itself as the target). The result is recorded as
+    /// a [`CertificationResult`], carrying the particular `user_id` it was made over — a key with
+    /// more than one user ID can have certifications on some but not others, and a caller building
+    /// a web-of-trust computation on top needs to know which identity each result backs, not just
+    /// the key as a whole.
+    pub fn verify_certifications(
+        &self,
+        target_key: &impl PublicKeyTrait,
+        certifiers: &[SignedPublicKey],
+    ) -> Vec<CertificationResult> {
+        let mut results = Vec::new();
+
+        for user in &self.users {
+            for sig in &user.signatures {
+                if Self::issued_by(sig, target_key) {
+                    continue;
+                }
+
+                let Some(certifier) = certifiers
+                    .iter()
+                    .find(|certifier| Self::issued_by(sig, *certifier))
+                else {
+                    continue;
+                };
+
+                let verified = sig
+                    .verify_certification(certifier, target_key, &user.id)
+                    .is_ok();
+
+                results.push(CertificationResult {
+                    user_id: user.id.clone(),
+                    certifier: certifier.fingerprint(),
+                    trust: sig.trust_signature(),
+                    verified,
+                });
+            }
+        }
+
+        results
+    }
+
+    /// Verifies this key against `policy`, considering only binding signatures already created
+    /// at the reference time `at`.
+    ///
+    /// This runs the same cryptographic checks as [`Self::verify`], but first filters every
+    /// signature through `policy`, skipping (rather than accepting) any that do not conform —
+    /// for example a self-signature made with a SHA-1 digest under [`StandardPolicy`]. A
+    /// self-signature created after `at` is skipped too, the same way [`Self::users_at`] ignores
+    /// it. A user ID or attribute left with no policy-conforming, not-yet-future binding
+    /// signature is treated as unverified. `key` itself is checked against the policy too, so
+    /// e.g. an undersized RSA key is rejected outright.
+    ///
+    /// This does not check [`Self::alive`] — revocation and expiry are orthogonal to whether the
+    /// signatures present are cryptographically valid and policy-conforming. Callers that also
+    /// care whether the key is currently usable should check `alive` separately, at whatever
+    /// reference time is appropriate for them.
+    pub fn verify_with_policy(
+        &self,
+        key: &impl PublicKeyTrait,
+        policy: &impl Policy,
+        at: DateTime<Utc>,
+    ) -> Result<()> {
+        policy.accept_key(key)?;
+
+        self.verify_users_with_policy(key, policy, at)?;
+        self.verify_attributes_with_policy(key, policy, at)?;
+        self.verify_revocation_signatures_with_policy(key, policy)?;
+        self.verify_direct_signatures_with_policy(key, policy)?;
 
         Ok(())
     }
 
-    pub fn as_unsigned(&self) -> KeyDetails {
-        if let Some(primary_user) = self
+    /// Returns whether this key was valid at the reference time `at`: neither revoked nor
+    /// expired, as of that instant.
+    ///
+    /// This combines [`Self::revocation_status`] with [`Self::key_expiration_time`] (measured
+    /// from `key`'s creation time), so callers can ask "was this key valid on date X" rather than
+    /// only "is it expired right now".
+    pub fn alive(&self, key: &impl PublicKeyTrait, at: DateTime<Utc>) -> bool {
+        if matches!(self.revocation_status(key, at), RevocationStatus::Revoked(_)) {
+            return false;
+        }
+
+        match self.key_expiration_time() {
+            None => true,
+            Some(expiration) => at < *key.created_at() + expiration,
+        }
+    }
+
+    /// Returns the users bound to this key that are valid at the reference time `at`. User
+    /// attributes are not included.
+    ///
+    /// For each user, the *self*-signature with the most recent creation time at or before `at`
+    /// is chosen to represent its current binding — rather than simply the first signature found,
+    /// as [`Self::as_unsigned`] used to — so that preferences or key flags updated by a later
+    /// self-signature are picked up correctly. Third-party certifications (see
+    /// [`Self::verify_certifications`]) are never considered here, since they don't speak for
+    /// `key` itself and could otherwise be used to smuggle in attacker-chosen key flags or
+    /// preferences. A user whose newest such self-signature is a certification revocation is
+    /// dropped entirely. Chain `.primary()` or `.with_key_flag(..)` on the result to narrow
+    /// further.
+    pub fn users_at(&self, key: &impl PublicKeyTrait, at: DateTime<Utc>) -> UserIter<'_> {
+        let bindings = self
             .users
             .iter()
-            .find(|u| u.is_primary())
-            .map_or_else(|| self.users.first(), Some)
-        {
-            let primary_sig = primary_user
-                .signatures
-                .first()
-                .expect("invalid primary user");
+            .filter_map(|user| {
+                let newest = user
+                    .signatures
+                    .iter()
+                    .filter(|sig| Self::issued_by(sig, key))
+                    .filter(|sig| Self::created_at_or_before(sig, at))
+                    .filter(|sig| sig.verify_certification(key, key, &user.id).is_ok())
+                    .max_by_key(|sig| sig.created())?;
+
+                if newest.typ() == SignatureType::CertRevocation {
+                    None
+                } else {
+                    Some(UserBinding {
+                        user,
+                        signature: newest,
+                    })
+                }
+            })
+            .collect();
+
+        UserIter::new(bindings)
+    }
+
+    /// Builds an unsigned [`KeyDetails`] summary from this key's currently-valid primary user ID,
+    /// as self-signed by `key` (see [`Self::users_at`]).
+    pub fn as_unsigned(&self, key: &impl PublicKeyTrait) -> KeyDetails {
+        if let Some(primary_user) = self.users_at(key, Utc::now()).primary() {
+            let primary_sig = primary_user.signature();
             let keyflags = primary_sig.key_flags();
 
             let preferred_symmetric_algorithms =
@@ -166,6 +646,86 @@ impl SignedKeyDetails {
             )
         }
     }
+
+    /// Serializes this key the way [`Serialize::to_writer`] does, but skips any signature whose
+    /// `Exportable Certification` subpacket is present and `false` — so-called "local"
+    /// signatures, such as those produced by `gpg --lsign`, which must not be published to a
+    /// keyserver. A user or user attribute left with no exportable signature at all is omitted
+    /// entirely, since an unbound user ID is meaningless to a recipient.
+    pub fn to_writer_exportable<W: io::Write>(&self, writer: &mut W) -> Result<()> {
+        for sig in self.revocation_signatures.iter().filter(|sig| Self::exportable(sig)) {
+            packet::write_packet(writer, sig)?;
+        }
+
+        for sig in self.direct_signatures.iter().filter(|sig| Self::exportable(sig)) {
+            packet::write_packet(writer, sig)?;
+        }
+
+        for user in &self.users {
+            let signatures: Vec<_> = user
+                .signatures
+                .iter()
+                .filter(|sig| Self::exportable(sig))
+                .cloned()
+                .collect();
+
+            if signatures.is_empty() {
+                continue;
+            }
+
+            SignedUser {
+                id: user.id.clone(),
+                signatures,
+            }
+            .to_writer(writer)?;
+        }
+
+        for attr in &self.user_attributes {
+            let signatures: Vec<_> = attr
+                .signatures
+                .iter()
+                .filter(|sig| Self::exportable(sig))
+                .cloned()
+                .collect();
+
+            if signatures.is_empty() {
+                continue;
+            }
+
+            SignedUserAttribute {
+                attr: attr.attr.clone(),
+                signatures,
+            }
+            .to_writer(writer)?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes `subkey_packet` followed by `signatures`, dropping any non-exportable signature —
+    /// the subkey-binding equivalent of [`Self::to_writer_exportable`]. A subkey left with no
+    /// exportable binding signature at all is omitted entirely, since an unbound subkey carries
+    /// no usable key flags and is meaningless to a recipient.
+    fn write_exportable_subkey<W: io::Write>(
+        writer: &mut W,
+        subkey_packet: &impl Serialize,
+        signatures: &[packet::Signature],
+    ) -> Result<()> {
+        let exportable_signatures: Vec<_> =
+            signatures.iter().filter(|sig| Self::exportable(sig)).collect();
+
+        if exportable_signatures.is_empty() {
+            return Ok(());
+        }
+
+        subkey_packet.to_writer(writer)?;
+
+        for sig in exportable_signatures {
+            packet::write_packet(writer, sig)?;
+        }
+
+        Ok(())
+    }
 }
 
 impl Serialize for SignedKeyDetails {
@@ -230,6 +790,20 @@ impl PublicOrSecret {
         }
     }
 
+    /// Writes this key the way [`Self::to_armored_writer`] does, but dropping any signature that
+    /// is not exportable (see [`SignedKeyDetails::to_writer_exportable`]). Use this instead of
+    /// [`Self::to_armored_writer`] when publishing a key to a keyserver.
+    pub fn to_armored_writer_exportable(
+        &self,
+        writer: &mut impl io::Write,
+        opts: ArmorOptions<'_>,
+    ) -> Result<()> {
+        match self {
+            PublicOrSecret::Public(k) => k.to_armored_writer_exportable(writer, opts),
+            PublicOrSecret::Secret(k) => k.to_armored_writer_exportable(writer, opts),
+        }
+    }
+
     /// Panics if not a secret key.
     pub fn into_secret(self) -> SignedSecretKey {
         match self {
@@ -269,3 +843,204 @@ impl Serialize for PublicOrSecret {
         }
     }
 }
+
+/// Serializes a [`SignedPublicKey`] the way [`SignedPublicKey::to_armored_writer_exportable`]
+/// does: `primary_key`, then `details`, then each of `public_subkeys`, dropping non-exportable
+/// signatures throughout.
+struct ExportablePublicKey<'a>(&'a SignedPublicKey);
+
+impl Serialize for ExportablePublicKey<'_> {
+    fn to_writer<W: io::Write>(&self, writer: &mut W) -> Result<()> {
+        self.0.primary_key.to_writer(writer)?;
+        self.0.details.to_writer_exportable(writer)?;
+
+        for subkey in &self.0.public_subkeys {
+            SignedKeyDetails::write_exportable_subkey(writer, &subkey.key, &subkey.signatures)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Serializes a [`SignedSecretKey`] the way [`SignedSecretKey::to_armored_writer_exportable`]
+/// does: `primary_key`, then `details`, then each of `public_subkeys` and `secret_subkeys`,
+/// dropping non-exportable signatures throughout.
+struct ExportableSecretKey<'a>(&'a SignedSecretKey);
+
+impl Serialize for ExportableSecretKey<'_> {
+    fn to_writer<W: io::Write>(&self, writer: &mut W) -> Result<()> {
+        self.0.primary_key.to_writer(writer)?;
+        self.0.details.to_writer_exportable(writer)?;
+
+        for subkey in &self.0.public_subkeys {
+            SignedKeyDetails::write_exportable_subkey(writer, &subkey.key, &subkey.signatures)?;
+        }
+
+        for subkey in &self.0.secret_subkeys {
+            SignedKeyDetails::write_exportable_subkey(writer, &subkey.key, &subkey.signatures)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl SignedPublicKey {
+    /// Writes this key the way [`Self::to_armored_writer`] does, but dropping any signature that
+    /// is not exportable (see [`SignedKeyDetails::to_writer_exportable`]), on the primary key's
+    /// users/attributes as well as on each of `public_subkeys`. Use this instead of
+    /// [`Self::to_armored_writer`] when publishing a key to a keyserver.
+    pub fn to_armored_writer_exportable(
+        &self,
+        writer: &mut impl io::Write,
+        opts: ArmorOptions<'_>,
+    ) -> Result<()> {
+        crate::armor::write(
+            &ExportablePublicKey(self),
+            crate::armor::BlockType::PublicKey,
+            writer,
+            opts,
+        )
+    }
+}
+
+impl SignedSecretKey {
+    /// Writes this key the way [`Self::to_armored_writer`] does, but dropping any signature that
+    /// is not exportable (see [`SignedKeyDetails::to_writer_exportable`]), on the primary key's
+    /// users/attributes as well as on each of `public_subkeys`/`secret_subkeys`. Use this instead
+    /// of [`Self::to_armored_writer`] when publishing a key to a keyserver.
+    pub fn to_armored_writer_exportable(
+        &self,
+        writer: &mut impl io::Write,
+        opts: ArmorOptions<'_>,
+    ) -> Result<()> {
+        crate::armor::write(
+            &ExportableSecretKey(self),
+            crate::armor::BlockType::PrivateKey,
+            writer,
+            opts,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::Deserializable;
+
+    fn parse_fixture(bytes: &[u8]) -> SignedPublicKey {
+        SignedPublicKey::from_armor_single(bytes)
+            .expect("fixture should parse")
+            .0
+    }
+
+    #[test]
+    fn revocation_status_not_revoked_key_stays_not_revoked() {
+        let eve = parse_fixture(include_bytes!("tests/fixtures/eve-not-revoked.asc"));
+        let at = Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap();
+
+        assert_eq!(
+            eve.details.revocation_status(&eve.primary_key, at),
+            RevocationStatus::NotRevoked
+        );
+    }
+
+    #[test]
+    fn revocation_status_hard_revocation_before_and_after_creation() {
+        let eve = parse_fixture(include_bytes!("tests/fixtures/eve-revoked.asc"));
+
+        // The revocation signature was created on 2021-01-01. Before that, a hard revocation
+        // has not yet taken effect, but it is still suspicious: `CouldBe`.
+        let before = Utc.with_ymd_and_hms(2020, 6, 1, 0, 0, 0).unwrap();
+        assert!(matches!(
+            eve.details.revocation_status(&eve.primary_key, before),
+            RevocationStatus::CouldBe(_)
+        ));
+
+        // After its creation time, a hard revocation applies unconditionally.
+        let after = Utc.with_ymd_and_hms(2021, 6, 1, 0, 0, 0).unwrap();
+        assert!(matches!(
+            eve.details.revocation_status(&eve.primary_key, after),
+            RevocationStatus::Revoked(_)
+        ));
+    }
+
+    #[test]
+    fn users_at_ignores_self_signatures_not_yet_created() {
+        let dave = parse_fixture(include_bytes!("tests/fixtures/dave-two-selfsigs.asc"));
+
+        // Dave's only self-signature was created on 2020-06-01; before that, he has no valid
+        // user ID binding at all.
+        let before = Utc.with_ymd_and_hms(2020, 2, 1, 0, 0, 0).unwrap();
+        assert!(dave
+            .details
+            .users_at(&dave.primary_key, before)
+            .primary()
+            .is_none());
+
+        // From its creation time onward, the binding is valid.
+        let after = Utc.with_ymd_and_hms(2020, 7, 1, 0, 0, 0).unwrap();
+        let primary = dave
+            .details
+            .users_at(&dave.primary_key, after)
+            .primary()
+            .expect("binding should be valid after its creation time");
+        assert_eq!(
+            primary.signature().created(),
+            Some(&Utc.with_ymd_and_hms(2020, 6, 1, 0, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn verify_certifications_matches_and_verifies_third_party_cert() {
+        let alice = parse_fixture(include_bytes!("tests/fixtures/alice-with-third-party-cert.asc"));
+        let bob = parse_fixture(include_bytes!("tests/fixtures/bob-certifier.asc"));
+
+        let results = alice
+            .details
+            .verify_certifications(&alice.primary_key, &[bob.clone()]);
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].user_id, alice.details.users[0].id);
+        assert_eq!(results[0].certifier, bob.primary_key.fingerprint());
+        assert!(results[0].verified);
+    }
+
+    #[test]
+    fn to_writer_exportable_drops_local_signatures() {
+        let alice = parse_fixture(include_bytes!("tests/fixtures/alice-with-local-sig.asc"));
+
+        // Carol's signature on Alice's user ID is local-only (`gpg --lsign`), so it must not
+        // survive a round trip through the exportable serialization.
+        assert_eq!(alice.details.users[0].signatures.len(), 3);
+        assert!(!SignedKeyDetails::exportable(
+            alice.details.users[0].signatures.last().unwrap()
+        ));
+
+        let mut full = Vec::new();
+        alice.details.to_writer(&mut full).unwrap();
+
+        let mut exportable = Vec::new();
+        alice.details.to_writer_exportable(&mut exportable).unwrap();
+
+        assert!(exportable.len() < full.len());
+    }
+
+    #[test]
+    fn to_armored_writer_exportable_keeps_subkeys() {
+        let alice = parse_fixture(include_bytes!("tests/fixtures/alice-with-local-sig.asc"));
+        assert_eq!(alice.public_subkeys.len(), 1, "fixture should carry a subkey");
+
+        let mut out = Vec::new();
+        alice
+            .to_armored_writer_exportable(&mut out, ArmorOptions::default())
+            .expect("exportable export should succeed");
+
+        let exported = SignedPublicKey::from_armor_single(out.as_slice())
+            .expect("exported key should re-parse")
+            .0;
+
+        assert_eq!(exported.public_subkeys.len(), alice.public_subkeys.len());
+    }
+}